@@ -1,6 +1,7 @@
 use core::time;
 use std::{
     process::{Child, Command, Stdio},
+    sync::mpsc,
     thread::sleep,
 };
 
@@ -10,7 +11,12 @@ use tiny_http::{Method, Response, Server};
 
 use crate::{
     config::DotLocalConfig,
-    helpers::{spawn_dns_proxies, stop_all_dns_proxies, update_caddyfile},
+    helpers::{
+        build_status_report, check_backends, spawn_backends, spawn_config_watcher,
+        spawn_dns_proxies, stop_all_backends, stop_all_dns_proxies, update_caddyfile,
+        BackendProcess, StatusReport,
+    },
+    record::{Header, HeaderDirection, SpawnConfig},
 };
 
 #[derive(Subcommand, Debug)]
@@ -30,10 +36,11 @@ pub enum Commands {
     /// Stop server.
     Stop,
 
-    /// Add a proxy entry in the format `<domain>:<port>`. You can add
-    /// multiple records separated by space.
+    /// Add a proxy entry in the format `<domain>:<port>`, or a static root
+    /// with `<domain>=<dir>`. You can add multiple records separated by
+    /// space.
     ///
-    /// Eg. `dotlocalctl add adeton.local:3000 mangobase.local:3003`
+    /// Eg. `dotlocalctl add adeton.local:3000 docs.local=./site`
     Add {
         #[arg()]
         proxies: Vec<String>,
@@ -50,6 +57,10 @@ pub enum Commands {
     /// Removes all proxy entries
     RemoveAll,
 
+    /// Reports whether each record's backend is reachable, and whether
+    /// Caddy's dns-sd responders and spawned backend processes are alive.
+    Status,
+
     /// Enable access on your local network or just your local machine
     Access {
         #[arg()]
@@ -61,6 +72,84 @@ pub enum Commands {
         #[arg()]
         option: Https,
     },
+
+    /// Configure a backing process to launch alongside a proxy's domain, the
+    /// way `dotlocalctl start` can bring up both the proxy and the app.
+    ///
+    /// `--env` must come before the `--` separator - anything after `--` is
+    /// passed to the process verbatim as `args`, `--env` included.
+    ///
+    /// Eg. `dotlocalctl spawn adeton.local /usr/bin/node --env PORT=3000 -- index.mjs`
+    Spawn {
+        #[arg()]
+        domain: String,
+
+        #[arg()]
+        command: String,
+
+        #[arg(last = true)]
+        args: Vec<String>,
+
+        /// Environment variable to pass to the process, as `KEY=VALUE`. Can be repeated.
+        #[arg(long = "env", value_parser = parse_env_pair)]
+        envs: Vec<(String, String)>,
+    },
+
+    /// Add, override, or delete a request/response header for a domain's
+    /// reverse proxy. An empty value deletes the header.
+    ///
+    /// Eg. `dotlocalctl header adeton.local up Host example.com`
+    /// Eg. `dotlocalctl header adeton.local down X-Powered-By ""`
+    Header {
+        #[arg()]
+        domain: String,
+
+        #[arg()]
+        direction: HeaderDirectionArg,
+
+        #[arg()]
+        name: String,
+
+        #[arg(default_value = "")]
+        value: String,
+    },
+
+    /// Make a domain redirect to another URL instead of proxying.
+    ///
+    /// Eg. `dotlocalctl redirect old.local https://new.example.com`
+    Redirect {
+        #[arg()]
+        domain: String,
+
+        #[arg()]
+        to: String,
+
+        /// Use a permanent (301) redirect instead of a temporary (302) one
+        #[arg(long)]
+        permanent: bool,
+    },
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum HeaderDirectionArg {
+    Up,
+    Down,
+}
+
+impl From<&HeaderDirectionArg> for HeaderDirection {
+    fn from(direction: &HeaderDirectionArg) -> Self {
+        match direction {
+            HeaderDirectionArg::Up => HeaderDirection::Up,
+            HeaderDirectionArg::Down => HeaderDirection::Down,
+        }
+    }
+}
+
+fn parse_env_pair(raw: &str) -> Result<(String, String), String> {
+    match raw.split_once('=') {
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(format!("expected KEY=VALUE, got `{raw}`")),
+    }
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -102,9 +191,31 @@ impl Commands {
 
             Commands::RemoveAll => self.remove_all_proxies(),
 
+            Commands::Status => self.status(),
+
             Commands::Access { option } => self.change_access(option),
 
             Commands::Https { option } => self.change_https(option),
+
+            Commands::Spawn {
+                domain,
+                command,
+                args,
+                envs,
+            } => self.set_spawn(domain, command, args, envs),
+
+            Commands::Header {
+                domain,
+                direction,
+                name,
+                value,
+            } => self.set_header(domain, direction, name, value),
+
+            Commands::Redirect {
+                domain,
+                to,
+                permanent,
+            } => self.set_redirect(domain, to, *permanent),
         }
     }
 }
@@ -122,9 +233,30 @@ impl Commands {
 
     fn start_server(&self) {
         let server = Server::http(ADDR).unwrap();
-        let mut proxy_processes: Vec<Child> = self.start_proxy();
+        let (mut proxy_processes, mut backend_processes) = self.start_proxy();
+
+        let watch_interval = DotLocalConfig::get().config_watch_interval_secs;
+        let (config_changed_tx, config_changed_rx) = mpsc::channel();
+        spawn_config_watcher(time::Duration::from_secs(watch_interval), config_changed_tx);
+
+        loop {
+            if config_changed_rx.try_recv().is_ok() {
+                println!("[DotLocal] dotlocal.json changed, reloading");
+                let config = DotLocalConfig::get();
+                self.restart_proxy(&mut proxy_processes, &mut backend_processes, &config);
+            }
+
+            let request = match server.recv_timeout(time::Duration::from_secs(2)) {
+                Ok(Some(request)) => request,
+
+                Ok(None) => {
+                    check_backends(&mut backend_processes);
+                    continue;
+                }
+
+                Err(_) => continue,
+            };
 
-        for request in server.incoming_requests() {
             println!(
                 "[DotLocal] {} {} {}",
                 Local::now(),
@@ -140,11 +272,21 @@ impl Commands {
             match request.url() {
                 "/restart" => {
                     let config = DotLocalConfig::get();
-                    self.restart_proxy(&mut proxy_processes, &config);
+                    self.restart_proxy(&mut proxy_processes, &mut backend_processes, &config);
+                }
+
+                "/status" => {
+                    let config = DotLocalConfig::get();
+                    let report =
+                        build_status_report(&config, &mut proxy_processes, &mut backend_processes);
+                    let json = serde_json::to_string(&report).unwrap_or_default();
+
+                    _ = request.respond(Response::from_string(json));
+                    continue;
                 }
 
                 "/quit" => {
-                    self.quit(&mut proxy_processes);
+                    self.quit(&mut proxy_processes, &mut backend_processes);
                     break;
                 }
 
@@ -155,7 +297,7 @@ impl Commands {
         }
     }
 
-    fn start_proxy(&self) -> Vec<Child> {
+    fn start_proxy(&self) -> (Vec<Child>, Vec<BackendProcess>) {
         let config = DotLocalConfig::get();
 
         update_caddyfile(&config);
@@ -170,13 +312,15 @@ impl Commands {
             .expect("failed to start caddy");
 
         let dns_processes = spawn_dns_proxies(&config);
+        let backend_processes = spawn_backends(&config);
         println!("Started proxy successfully");
 
-        dns_processes
+        (dns_processes, backend_processes)
     }
 
-    fn quit(&self, processes: &mut Vec<Child>) {
+    fn quit(&self, processes: &mut Vec<Child>, backend_processes: &mut Vec<BackendProcess>) {
         stop_all_dns_proxies(processes);
+        stop_all_backends(backend_processes);
 
         // quit caddy
         Command::new(CADDY_BIN)
@@ -221,6 +365,98 @@ impl Commands {
     }
 }
 
+// Mark: Status
+
+impl Commands {
+    pub fn status(&self) {
+        let endpoint = format!("http://{ADDR}/status");
+
+        let response = match reqwest::blocking::get(endpoint) {
+            Ok(response) => response,
+            Err(_) => {
+                println!("dotlocalctl doesn't seem to be running");
+                return;
+            }
+        };
+
+        let report: StatusReport = match serde_json::from_str(&response.text().unwrap_or_default())
+        {
+            Ok(report) => report,
+            Err(_) => {
+                println!("failed to parse status response");
+                return;
+            }
+        };
+
+        println!("{:<28}{:<34}{:<8}{}", "DOMAIN", "TARGET", "STATUS", "LATENCY");
+        for record in &report.records {
+            let status = match record.reachable {
+                Some(true) => "up",
+                Some(false) => "down",
+                None => "n/a",
+            };
+
+            let latency = match record.latency_ms {
+                Some(ms) => format!("{ms}ms"),
+                None => "-".to_string(),
+            };
+
+            println!(
+                "{:<28}{:<34}{:<8}{}",
+                record.domain, record.target, status, latency
+            );
+        }
+
+        println!();
+        println!("dns-sd responders alive: {}", report.dns_responders_alive);
+        println!("backend processes alive: {}", report.backend_processes_alive);
+    }
+}
+
+// Mark: Spawn
+
+impl Commands {
+    pub fn set_spawn(&self, domain: &str, command: &str, args: &Vec<String>, envs: &Vec<(String, String)>) {
+        let mut config = DotLocalConfig::get();
+        config.set_spawn(
+            domain,
+            SpawnConfig {
+                command: command.to_string(),
+                args: args.clone(),
+                envs: envs.clone(),
+            },
+        );
+        println!("Configured backend process for {domain}");
+    }
+}
+
+// Mark: Header
+
+impl Commands {
+    pub fn set_header(&self, domain: &str, direction: &HeaderDirectionArg, name: &str, value: &str) {
+        let mut config = DotLocalConfig::get();
+        config.set_header(
+            domain,
+            Header {
+                name: name.to_string(),
+                value: value.to_string(),
+                direction: direction.into(),
+            },
+        );
+        println!("Set header for {domain}");
+    }
+}
+
+// Mark: Redirect
+
+impl Commands {
+    pub fn set_redirect(&self, domain: &str, to: &str, permanent: bool) {
+        let mut config = DotLocalConfig::get();
+        config.set_redirect(domain, to.to_string(), permanent);
+        println!("Added redirect for {domain}");
+    }
+}
+
 // Mark: Access
 
 impl Commands {
@@ -272,9 +508,15 @@ impl Commands {
 // Mark: Restart
 
 impl Commands {
-    pub fn restart_proxy(&self, processes: &mut Vec<Child>, config: &DotLocalConfig) {
+    pub fn restart_proxy(
+        &self,
+        processes: &mut Vec<Child>,
+        backend_processes: &mut Vec<BackendProcess>,
+        config: &DotLocalConfig,
+    ) {
         update_caddyfile(&config);
         stop_all_dns_proxies(processes);
+        stop_all_backends(backend_processes);
 
         Command::new(CADDY_BIN)
             .arg("reload")
@@ -282,8 +524,10 @@ impl Commands {
             .expect("failed to reload caddy");
 
         let mut new_processes = spawn_dns_proxies(&config);
-
         processes.append(&mut new_processes);
+
+        let mut new_backends = spawn_backends(&config);
+        backend_processes.append(&mut new_backends);
     }
 }
 
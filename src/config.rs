@@ -6,13 +6,22 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::record::Record;
+use crate::record::{Header, Record, SpawnConfig, Target};
+
+fn default_config_watch_interval_secs() -> u64 {
+    3
+}
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DotLocalConfig {
     pub records: HashMap<String, Record>,
     pub automatic_https_redirect: bool,
     pub lan_enabled: bool,
+
+    /// How often, in seconds, the running server polls `dotlocal.json` for
+    /// changes to hot-reload.
+    #[serde(default = "default_config_watch_interval_secs")]
+    pub config_watch_interval_secs: u64,
 }
 
 impl DotLocalConfig {
@@ -21,6 +30,7 @@ impl DotLocalConfig {
             records: HashMap::new(),
             automatic_https_redirect: true,
             lan_enabled: true,
+            config_watch_interval_secs: default_config_watch_interval_secs(),
         }
     }
 
@@ -58,21 +68,40 @@ impl DotLocalConfig {
 impl DotLocalConfig {
     pub fn add_proxies(&mut self, entries: &Vec<String>) {
         for entry in entries {
-            let (domain, port, path) = DotLocalConfig::parse_proxy_entry(entry);
+            let (domain, target, path) = DotLocalConfig::parse_proxy_entry(entry);
 
             let existing_entry = self.records.get_mut(domain);
 
-            let (port, mut paths): (i32, Vec<(String, i32)>) = match path {
-                Some(rest) => (-1, vec![(format!("/{rest}"), port)]),
+            let (target, mut paths): (Target, Vec<(String, i32)>) = match path {
+                Some(rest) => {
+                    let port = match target {
+                        Target::Tcp(port) => port,
+
+                        Target::Unix(_) => {
+                            println!(
+                                "{domain}/{rest}: unix socket targets aren't supported for path-scoped entries, skipping"
+                            );
+                            continue;
+                        }
+
+                        // `parse_proxy_entry` only ever produces `Tcp`/`Unix` for a
+                        // path-scoped (`domain/path:target`) entry.
+                        _ => unreachable!(
+                            "parse_proxy_entry only produces Tcp/Unix targets for path-scoped entries"
+                        ),
+                    };
 
-                None => (port, vec![]),
+                    (Target::None, vec![(format!("/{rest}"), port)])
+                }
+
+                None => (target, vec![]),
             };
 
             match existing_entry {
                 Some(config) => {
                     if paths.is_empty() {
-                        // port changed
-                        config.port = port
+                        // target changed
+                        config.target = target
                     } else {
                         // removes previous entries of this path
                         config.paths.retain(|it| it.0 != paths[0].0);
@@ -84,7 +113,9 @@ impl DotLocalConfig {
                     let record = Record {
                         domain: domain.to_string(),
                         paths,
-                        port,
+                        target,
+                        headers: vec![],
+                        spawn: None,
                     };
 
                     self.records.insert(domain.to_string(), record);
@@ -99,24 +130,36 @@ impl DotLocalConfig {
         let mut config = DotLocalConfig::get();
 
         for entry in entries {
-            let (domain, port, path) = DotLocalConfig::parse_proxy_entry(entry);
+            // a bare domain (no `:port`/`=dir`) removes that domain's whole
+            // record - the only way to undo a `redirect`, which has no
+            // entry syntax of its own to match against.
+            if !entry.contains(':') && !entry.contains('=') {
+                config.records.remove(entry.as_str());
+                continue;
+            }
+
+            let (domain, target, path) = DotLocalConfig::parse_proxy_entry(entry);
             if let Some(existing) = config.records.get_mut(domain) {
                 match path {
                     Some(path) => {
                         let path = format!("/{path}");
+                        let port = match target {
+                            Target::Tcp(port) => port,
+                            _ => continue,
+                        };
                         existing.paths.retain(|it| it.0 != path || it.1 != port);
 
-                        if existing.paths.is_empty() && existing.port == -1 {
+                        if existing.paths.is_empty() && existing.target == Target::None {
                             config.records.remove(domain);
                         }
                     }
 
                     None => {
-                        if port == existing.port {
+                        if target == existing.target {
                             if existing.paths.is_empty() {
                                 config.records.remove(domain);
                             } else {
-                                existing.port = -1
+                                existing.target = Target::None
                             }
                         }
                     }
@@ -124,6 +167,50 @@ impl DotLocalConfig {
             }
         }
 
+        config.save();
+    }
+
+    pub fn set_spawn(&mut self, domain: &str, spawn: SpawnConfig) {
+        match self.records.get_mut(domain) {
+            Some(record) => {
+                record.spawn = Some(spawn);
+                self.save();
+            }
+
+            None => println!("no record found for {domain}, add a proxy first"),
+        }
+    }
+
+    pub fn set_header(&mut self, domain: &str, header: Header) {
+        match self.records.get_mut(domain) {
+            Some(record) => {
+                // replace any existing directive for the same name/direction
+                record
+                    .headers
+                    .retain(|h| !(h.name == header.name && h.direction == header.direction));
+                record.headers.push(header);
+
+                self.save();
+            }
+
+            None => println!("no record found for {domain}, add a proxy first"),
+        }
+    }
+
+    pub fn set_redirect(&mut self, domain: &str, to: String, permanent: bool) {
+        let record = self.records.entry(domain.to_string()).or_insert_with(|| Record {
+            domain: domain.to_string(),
+            paths: vec![],
+            target: Target::None,
+            headers: vec![],
+            spawn: None,
+        });
+
+        // a redirect always wins for the whole domain - Caddy evaluates
+        // `redir` ahead of `reverse_proxy` regardless of write order, so any
+        // path-scoped proxies would be unreachable anyway.
+        record.target = Target::Redirect { to, permanent };
+        record.paths.clear();
         self.save();
     }
 
@@ -131,16 +218,27 @@ impl DotLocalConfig {
         let mut config = DotLocalConfig::get();
         config.records = HashMap::new();
 
-        self.save();
+        config.save();
     }
 
-    fn parse_proxy_entry(entry: &String) -> (&str, i32, Option<&str>) {
-        let parts: Vec<_> = entry.split(':').collect();
+    fn parse_proxy_entry(entry: &String) -> (&str, Target, Option<&str>) {
+        // `domain=./dir` registers a static root instead of a proxy target;
+        // it has no path-scoped form.
+        if let Some((domain, root)) = entry.split_once('=') {
+            return (domain, Target::Static(root.trim().to_string()), None);
+        }
+
+        let parts: Vec<_> = entry.splitn(2, ':').collect();
         let url = parts[0];
-        let port: i32 = parts[1]
-            .trim()
-            .parse()
-            .expect("port part should be a number");
+        let target_part = parts[1].trim();
+
+        let target = match target_part.strip_prefix("unix/") {
+            Some(socket_path) => Target::Unix(socket_path.to_string()),
+            None => {
+                let port: i32 = target_part.parse().expect("port part should be a number");
+                Target::Tcp(port)
+            }
+        };
 
         let url_parts: Vec<_> = url.splitn(2, '/').collect();
         let domain = url_parts[0];
@@ -149,7 +247,7 @@ impl DotLocalConfig {
             None => None,
         };
 
-        return (domain, port, path);
+        return (domain, target, path);
     }
 
     pub fn save(&self) {
@@ -1,6 +1,21 @@
 use crate::config::DotLocalConfig;
+use crate::record::{Record, SpawnConfig, Target};
 use local_ip_address::local_ip;
-use std::{str::FromStr, fs::OpenOptions, io::Write, process::Child, collections::HashSet};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::Write,
+    net::{SocketAddr, TcpStream},
+    process::{Child, Command},
+    str::FromStr,
+    sync::mpsc::Sender,
+    thread::{self, sleep},
+    time::{Duration, Instant, SystemTime},
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
 
 pub fn get_ip(lan_enabled: bool) -> String {
     if lan_enabled {
@@ -74,3 +89,319 @@ pub fn stop_all_dns_proxies(processes: &mut Vec<Child>) {
 
     processes.clear();
 }
+
+/// A backend process spawned to back a proxy entry, kept alongside its
+/// `spawn` config so it can be restarted if it dies.
+pub struct BackendProcess {
+    pub domain: String,
+    spawn: SpawnConfig,
+    child: Child,
+    attempts: u32,
+    /// When to try the next restart, set instead of blocking the caller in
+    /// `sleep` so `check_backends` always returns immediately.
+    next_retry_at: Option<Instant>,
+    /// When the current child last (re)started successfully; used to decide
+    /// it has run long enough to forgive past restart attempts.
+    healthy_since: Option<Instant>,
+}
+
+const MAX_BACKEND_RESTART_ATTEMPTS: u32 = 5;
+
+/// How long a backend has to stay up before its restart `attempts` counter
+/// is forgiven, so an occasional crash doesn't permanently burn through a
+/// long-lived daemon's lifetime restart budget.
+const BACKEND_HEALTHY_RESET_AFTER: Duration = Duration::from_secs(30);
+
+fn spawn_backend_process(spawn: &SpawnConfig) -> Result<Child, std::io::Error> {
+    let mut command = Command::new(&spawn.command);
+    command.args(&spawn.args);
+
+    for (key, value) in &spawn.envs {
+        command.env(key, value);
+    }
+
+    command.spawn()
+}
+
+pub fn spawn_backends(config: &DotLocalConfig) -> Vec<BackendProcess> {
+    let records = config.records_list();
+
+    let mut processes: Vec<BackendProcess> = vec![];
+    for record in records.into_iter() {
+        let spawn = match record.spawn {
+            Some(spawn) => spawn,
+            None => continue,
+        };
+
+        match spawn_backend_process(&spawn) {
+            Ok(child) => processes.push(BackendProcess {
+                domain: record.domain,
+                spawn,
+                child,
+                attempts: 0,
+                next_retry_at: None,
+                healthy_since: Some(Instant::now()),
+            }),
+
+            Err(_) => println!("error spawning backend process for {}", record.domain),
+        }
+    }
+
+    processes
+}
+
+/// Checks each backend process for a crash and restarts it with a simple
+/// exponential backoff, up to `MAX_BACKEND_RESTART_ATTEMPTS` tries. Called on
+/// every idle tick of the control server's loop, so this never blocks: a
+/// pending restart is recorded as a `next_retry_at` instant rather than
+/// slept through, keeping `/restart`, `/status`, and `/quit` responsive
+/// while backends are down.
+pub fn check_backends(processes: &mut Vec<BackendProcess>) {
+    let now = Instant::now();
+
+    for process in processes.iter_mut() {
+        let exited = matches!(process.child.try_wait(), Ok(Some(_)));
+
+        if !exited {
+            if let Some(healthy_since) = process.healthy_since {
+                if process.attempts > 0 && now.duration_since(healthy_since) >= BACKEND_HEALTHY_RESET_AFTER
+                {
+                    process.attempts = 0;
+                }
+            }
+            continue;
+        }
+
+        process.healthy_since = None;
+
+        if process.attempts >= MAX_BACKEND_RESTART_ATTEMPTS {
+            continue;
+        }
+
+        let retry_at = match process.next_retry_at {
+            Some(retry_at) => retry_at,
+
+            None => {
+                let backoff = Duration::from_secs(1 << process.attempts.min(4));
+                println!(
+                    "backend for {} exited, restarting in {:?}",
+                    process.domain, backoff
+                );
+                let retry_at = now + backoff;
+                process.next_retry_at = Some(retry_at);
+                retry_at
+            }
+        };
+
+        if now < retry_at {
+            continue;
+        }
+
+        match spawn_backend_process(&process.spawn) {
+            Ok(child) => {
+                process.child = child;
+                process.attempts += 1;
+                process.next_retry_at = None;
+                process.healthy_since = Some(now);
+            }
+
+            Err(_) => {
+                process.attempts += 1;
+                process.next_retry_at = None;
+                println!("error respawning backend process for {}", process.domain);
+            }
+        }
+    }
+}
+
+pub fn stop_all_backends(processes: &mut Vec<BackendProcess>) {
+    for process in processes.iter_mut() {
+        _ = process.child.kill();
+        // reap the child so it doesn't linger as a zombie - with the config
+        // watcher (chunk0-3) hot-reloading, this runs far more often than a
+        // one-shot `stop`.
+        _ = process.child.wait();
+    }
+
+    processes.clear();
+}
+
+impl BackendProcess {
+    pub fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+/// Reachability of a single record's backend, as reported by `/status`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordStatus {
+    pub domain: String,
+    pub target: String,
+    /// `None` when the record has no backend to probe (a static root, or a
+    /// record with only path-scoped proxies).
+    pub reachable: Option<bool>,
+    pub latency_ms: Option<u128>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusReport {
+    pub records: Vec<RecordStatus>,
+    pub dns_responders_alive: usize,
+    pub backend_processes_alive: usize,
+}
+
+fn probe_tcp(addr: &str) -> (bool, Option<u128>) {
+    let socket_addr: SocketAddr = match addr.parse() {
+        Ok(addr) => addr,
+        Err(_) => return (false, None),
+    };
+
+    let start = Instant::now();
+    match TcpStream::connect_timeout(&socket_addr, Duration::from_secs(2)) {
+        Ok(_) => (true, Some(start.elapsed().as_millis())),
+        Err(_) => (false, None),
+    }
+}
+
+#[cfg(unix)]
+fn probe_unix(path: &str) -> (bool, Option<u128>) {
+    let start = Instant::now();
+    match UnixStream::connect(path) {
+        Ok(_) => (true, Some(start.elapsed().as_millis())),
+        Err(_) => (false, None),
+    }
+}
+
+#[cfg(not(unix))]
+fn probe_unix(_path: &str) -> (bool, Option<u128>) {
+    (false, None)
+}
+
+fn probe_record(record: &Record, ip: &str) -> RecordStatus {
+    let (target, probe) = match &record.target {
+        Target::Tcp(port) => {
+            let addr = format!("{ip}:{port}");
+            let probe = probe_tcp(&addr);
+            (format!("tcp:{addr}"), Some(probe))
+        }
+
+        Target::Unix(socket_path) => {
+            let probe = probe_unix(socket_path);
+            (format!("unix:{socket_path}"), Some(probe))
+        }
+
+        Target::Static(root) => (format!("static:{root}"), None),
+
+        Target::Redirect { to, .. } => (format!("redirect:{to}"), None),
+
+        Target::None => ("none".to_string(), None),
+    };
+
+    let (reachable, latency_ms) = match probe {
+        Some((reachable, latency_ms)) => (Some(reachable), latency_ms),
+        None => (None, None),
+    };
+
+    RecordStatus {
+        domain: record.domain.clone(),
+        target,
+        reachable,
+        latency_ms,
+    }
+}
+
+/// Builds the `/status` report: per-record backend reachability, plus
+/// whether the dns-sd responders and spawned backend processes are alive.
+///
+/// Each record is probed on its own thread (`probe_tcp` carries a 2s connect
+/// timeout; `probe_unix` has none), so a report over many unreachable records
+/// still takes about as long as the slowest single probe rather than
+/// `records.len() * timeout` on the control server's event-loop thread.
+pub fn build_status_report(
+    config: &DotLocalConfig,
+    dns_processes: &mut Vec<Child>,
+    backend_processes: &mut Vec<BackendProcess>,
+) -> StatusReport {
+    let ip = get_ip(config.lan_enabled);
+    let records_list = config.records_list();
+
+    let records: Vec<RecordStatus> = thread::scope(|scope| {
+        let handles: Vec<_> = records_list
+            .iter()
+            .map(|record| scope.spawn(|| probe_record(record, ip.as_str())))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("status probe thread panicked"))
+            .collect()
+    });
+
+    let dns_responders_alive = dns_processes
+        .iter_mut()
+        .filter(|process| matches!(process.try_wait(), Ok(None)))
+        .count();
+
+    let backend_processes_alive = backend_processes
+        .iter_mut()
+        .filter(|process| process.is_alive())
+        .count();
+
+    StatusReport {
+        records,
+        dns_responders_alive,
+        backend_processes_alive,
+    }
+}
+
+const CONFIG_PATH: &str = "./dotlocal.json";
+
+/// `(size, mtime)` of `dotlocal.json`, cheap enough to poll and good enough
+/// to tell "something changed" apart from "nothing changed".
+type ConfigSnapshot = Option<(u64, SystemTime)>;
+
+fn config_snapshot() -> ConfigSnapshot {
+    let metadata = fs::metadata(CONFIG_PATH).ok()?;
+    let modified = metadata.modified().ok()?;
+
+    Some((metadata.len(), modified))
+}
+
+/// The watcher won't poll faster than this, regardless of
+/// `config_watch_interval_secs` - a `0` there would otherwise busy-spin the
+/// thread.
+const MIN_CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls `dotlocal.json` on a background thread and notifies `on_change`
+/// once a modification has settled, so `add`/`remove`/`spawn` take effect
+/// without the user issuing a separate `restart`. A change is only reported
+/// once two consecutive reads, a beat apart, agree - this avoids reacting
+/// to a file that's still being written.
+pub fn spawn_config_watcher(interval: Duration, on_change: Sender<()>) {
+    let interval = interval.max(MIN_CONFIG_WATCH_INTERVAL);
+
+    thread::spawn(move || {
+        let mut last_seen = config_snapshot();
+
+        loop {
+            sleep(interval);
+
+            let first = config_snapshot();
+            if first == last_seen {
+                continue;
+            }
+
+            sleep(Duration::from_millis(200));
+
+            let second = config_snapshot();
+            if first != second {
+                continue;
+            }
+
+            last_seen = second;
+            if on_change.send(()).is_err() {
+                break;
+            }
+        }
+    });
+}
@@ -3,11 +3,78 @@ use std::process::{Command, Child};
 
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SpawnConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub envs: Vec<(String, String)>,
+}
+
+/// What a record's top-level domain proxies to. `paths` (path-scoped
+/// entries) are always TCP and keep using a plain port, since Caddy's
+/// `unix/` target isn't meaningful split across multiple paths.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub enum Target {
+    Tcp(i32),
+    Unix(String),
+    /// Serve files from a directory instead of proxying, e.g. a built SPA.
+    Static(String),
+    /// Redirect to another URL instead of proxying or serving anything.
+    Redirect { to: String, permanent: bool },
+    None,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub enum HeaderDirection {
+    Up,
+    Down,
+}
+
+impl HeaderDirection {
+    fn directive(&self) -> &'static str {
+        match self {
+            HeaderDirection::Up => "header_up",
+            HeaderDirection::Down => "header_down",
+        }
+    }
+}
+
+/// A header to add, override, or delete on the proxied request/response. An
+/// empty `value` deletes the header (`header_up -Name`).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+    pub direction: HeaderDirection,
+}
+
+impl Header {
+    fn line(&self) -> String {
+        let directive = self.direction.directive();
+
+        if self.value.is_empty() {
+            format!("\n\t\t{directive} -{}", self.name)
+        } else {
+            format!("\n\t\t{directive} {} {}", self.name, self.value)
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Record {
     pub domain: String,
     pub paths: Vec<(String, i32)>,
-    pub port: i32,
+    pub target: Target,
+
+    /// Header directives to apply to this record's reverse_proxy block.
+    #[serde(default)]
+    pub headers: Vec<Header>,
+
+    /// The backing process to launch alongside this proxy, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn: Option<SpawnConfig>,
 }
 
 impl Record {
@@ -24,10 +91,30 @@ impl Record {
             res.push_str(domain_line.as_str());
         }
 
-        let port = self.port;
-        if port > -1 {
-            let port_entry = format!("\n\treverse_proxy {ip}:{port}");
-            res.push_str(port_entry.as_str());
+        match &self.target {
+            Target::Tcp(port) => {
+                res.push_str(self.reverse_proxy_entry(format!("{ip}:{port}")).as_str());
+            }
+
+            Target::Unix(socket_path) => {
+                res.push_str(
+                    self.reverse_proxy_entry(format!("unix/{socket_path}"))
+                        .as_str(),
+                );
+            }
+
+            Target::Static(root) => {
+                let static_entry = format!("\n\troot * {root}\n\tfile_server");
+                res.push_str(static_entry.as_str());
+            }
+
+            Target::Redirect { to, permanent } => {
+                let code = if *permanent { " permanent" } else { "" };
+                let redir_entry = format!("\n\tredir {to}{{uri}}{code}");
+                res.push_str(redir_entry.as_str());
+            }
+
+            Target::None => {}
         }
 
         for (path, port) in &self.paths {
@@ -40,6 +127,20 @@ impl Record {
         res
     }
 
+    fn reverse_proxy_entry(&self, upstream: String) -> String {
+        if self.headers.is_empty() {
+            return format!("\n\treverse_proxy {upstream}");
+        }
+
+        let mut entry = format!("\n\treverse_proxy {upstream} {{");
+        for header in &self.headers {
+            entry.push_str(header.line().as_str());
+        }
+        entry.push_str("\n\t}");
+
+        entry
+    }
+
     pub fn spawn_dns_proxy(&self, ip: &str) -> Result<Child, std::io::Error> {
         let name = self.domain.trim_end_matches(".local");
 